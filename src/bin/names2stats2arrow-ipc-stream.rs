@@ -1,26 +1,111 @@
 use std::io::{self, Error};
 use std::sync::Arc;
 
+use arrow::array::{
+    BooleanArray, Datum, Scalar, StringArray, TimestampNanosecondArray, UInt32Array, UInt64Array,
+};
+use arrow::compute::filter_record_batch;
+use arrow::compute::kernels::cmp::{eq, gt, lt};
+use arrow::error::ArrowError;
+use arrow::datatypes::{DataType, TimeUnit};
+use arrow::ipc::reader::StreamReader;
 use arrow::ipc::writer::StreamWriter;
-use clap::Parser;
+use arrow::record_batch::RecordBatch;
+use clap::{Args, Parser, Subcommand};
 
-use rs_names2stats2arrow_ipc_stream::{lines2batch_iter, schema, stdin2lines};
+use rs_names2stats2arrow_ipc_stream::{
+    lines2batch_iter, schema, stdin2lines, tar_entries2batch_iter, walk,
+};
+
+/// A fallible stream of `(path, traversal depth)` pairs feeding `lines2batch_iter`.
+type LineSource = Box<dyn Iterator<Item = Result<(String, Option<u32>), Error>>>;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(long, default_value_t = 1024)]
     batch_size: usize,
+
+    /// Expand directory inputs into their descendants.
+    #[arg(long)]
+    recursive: bool,
+
+    /// Limit how many levels below each root are visited (implies --recursive).
+    #[arg(long)]
+    max_depth: Option<u32>,
+
+    /// Treat symlinks to directories as directories while walking.
+    #[arg(long)]
+    follow_symlinks: bool,
+
+    /// List and capture each path's extended attributes into the `xattrs` column.
+    #[arg(long)]
+    xattrs: bool,
+
+    /// Inventory a tar stream read from stdin instead of stat'ing local paths.
+    #[arg(long)]
+    from_tar: bool,
+
+    /// Do not follow symlinks; record the link itself and its resolved target.
+    #[arg(long)]
+    no_follow: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Read an Arrow IPC stream from stdin, optionally project/filter, re-emit it.
+    Read(ReadArgs),
+}
+
+#[derive(Args)]
+struct ReadArgs {
+    /// Comma-separated column names to keep (default: all columns).
+    #[arg(long)]
+    columns: Option<String>,
+
+    /// Row predicate `col OP value` (OP is one of `=`, `>`, `<`); repeatable, ANDed.
+    #[arg(long)]
+    filter: Vec<String>,
 }
 
 fn main() -> Result<(), Error> {
     let cli = Cli::parse();
 
+    if let Some(Command::Read(args)) = cli.command {
+        return run_read(&args);
+    }
+
     let schema = schema();
     let mut writer = StreamWriter::try_new(io::stdout(), &schema).map_err(io::Error::other)?;
 
+    if cli.from_tar {
+        let batch_iter =
+            tar_entries2batch_iter(io::stdin().lock(), Arc::clone(&schema), cli.batch_size)?;
+        for rbat in batch_iter {
+            writer.write(&rbat?).map_err(io::Error::other)?;
+        }
+        writer.finish().map_err(io::Error::other)?;
+        return Ok(());
+    }
+
+    let recursive = cli.recursive || cli.max_depth.is_some();
     let lines = stdin2lines();
-    let batch_iter = lines2batch_iter(lines, Arc::clone(&schema), cli.batch_size)?;
+    let lines: LineSource = if recursive {
+        Box::new(walk(lines, cli.max_depth, cli.follow_symlinks))
+    } else {
+        Box::new(lines.map(|r| r.map(|s| (s, None))))
+    };
+    let batch_iter = lines2batch_iter(
+        lines,
+        Arc::clone(&schema),
+        cli.batch_size,
+        cli.xattrs,
+        cli.no_follow,
+        recursive,
+    )?;
 
     for rbat in batch_iter {
         let bat = rbat?;
@@ -30,3 +115,180 @@ fn main() -> Result<(), Error> {
     writer.finish().map_err(io::Error::other)?;
     Ok(())
 }
+
+fn run_read(args: &ReadArgs) -> Result<(), Error> {
+    let reader = StreamReader::try_new(io::stdin().lock(), None).map_err(io::Error::other)?;
+    let in_schema = reader.schema();
+
+    let projection: Option<Vec<usize>> = match &args.columns {
+        Some(cols) => Some(
+            cols.split(',')
+                .map(|c| in_schema.index_of(c.trim()).map_err(io::Error::other))
+                .collect::<Result<_, _>>()?,
+        ),
+        None => None,
+    };
+
+    let out_schema = match &projection {
+        Some(idx) => Arc::new(in_schema.project(idx).map_err(io::Error::other)?),
+        None => Arc::clone(&in_schema),
+    };
+
+    let mut writer = StreamWriter::try_new(io::stdout(), &out_schema).map_err(io::Error::other)?;
+
+    for rbat in reader {
+        let mut batch = rbat.map_err(io::Error::other)?;
+        // Predicates are evaluated against the full schema, so a filter may
+        // reference a column that the projection later drops.
+        for expr in &args.filter {
+            let mask = compile_predicate(&batch, expr)?;
+            batch = filter_record_batch(&batch, &mask).map_err(io::Error::other)?;
+        }
+        if let Some(idx) = &projection {
+            batch = batch.project(idx).map_err(io::Error::other)?;
+        }
+        writer.write(&batch).map_err(io::Error::other)?;
+    }
+
+    writer.finish().map_err(io::Error::other)?;
+    Ok(())
+}
+
+#[derive(Debug)]
+enum Op {
+    Eq,
+    Gt,
+    Lt,
+}
+
+/// Splits `col OP value` into its parts, picking the first `=`, `>` or `<`.
+fn split_predicate(expr: &str) -> Result<(&str, Op, &str), Error> {
+    if let Some((pos, ch)) = expr
+        .char_indices()
+        .find(|&(_, c)| matches!(c, '=' | '>' | '<'))
+    {
+        let op = match ch {
+            '=' => Op::Eq,
+            '>' => Op::Gt,
+            _ => Op::Lt,
+        };
+        return Ok((expr[..pos].trim(), op, expr[pos + 1..].trim()));
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("invalid filter expression: {expr}"),
+    ))
+}
+
+fn parse_num<T>(v: &str) -> Result<T, Error>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    v.parse::<T>()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))
+}
+
+fn cmp(op: &Op, lhs: &dyn Datum, rhs: &dyn Datum) -> Result<BooleanArray, ArrowError> {
+    match op {
+        Op::Eq => eq(lhs, rhs),
+        Op::Gt => gt(lhs, rhs),
+        Op::Lt => lt(lhs, rhs),
+    }
+}
+
+/// Compiles a single `col OP value` predicate into a boolean mask over `batch`.
+fn compile_predicate(batch: &RecordBatch, expr: &str) -> Result<BooleanArray, Error> {
+    let (col, op, value) = split_predicate(expr)?;
+    let idx = batch.schema().index_of(col).map_err(io::Error::other)?;
+    let array = batch.column(idx);
+
+    let mask = match array.data_type() {
+        DataType::Utf8 => cmp(&op, array, &Scalar::new(StringArray::from(vec![value]))),
+        DataType::UInt64 => {
+            let v: u64 = parse_num(value)?;
+            cmp(&op, array, &Scalar::new(UInt64Array::from(vec![v])))
+        }
+        DataType::UInt32 => {
+            let v: u32 = parse_num(value)?;
+            cmp(&op, array, &Scalar::new(UInt32Array::from(vec![v])))
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            let v: i64 = parse_num(value)?;
+            cmp(&op, array, &Scalar::new(TimestampNanosecondArray::from(vec![v])))
+        }
+        other => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unsupported column type for filter: {other:?}"),
+            ))
+        }
+    };
+    mask.map_err(io::Error::other)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{ArrayRef, BooleanArray as BA};
+    use arrow::datatypes::{Field, Schema};
+
+    fn batch(name: &str, array: ArrayRef) -> RecordBatch {
+        let schema = Schema::new(vec![Field::new(name, array.data_type().clone(), false)]);
+        RecordBatch::try_new(Arc::new(schema), vec![array]).unwrap()
+    }
+
+    fn mask(expr: &str, array: ArrayRef) -> Vec<bool> {
+        let bat = batch("col", array);
+        let m: BA = compile_predicate(&bat, expr).unwrap();
+        m.iter().map(|v| v.unwrap()).collect()
+    }
+
+    #[test]
+    fn split_picks_first_operator() {
+        let (col, op, val) = split_predicate("len > 10").unwrap();
+        assert_eq!(col, "len");
+        assert!(matches!(op, Op::Gt));
+        assert_eq!(val, "10");
+    }
+
+    #[test]
+    fn utf8_equality() {
+        let arr = Arc::new(StringArray::from(vec!["a", "b", "a"]));
+        assert_eq!(mask("col = a", arr), vec![true, false, true]);
+    }
+
+    #[test]
+    fn uint64_comparisons() {
+        let arr = Arc::new(UInt64Array::from(vec![1u64, 5, 9]));
+        assert_eq!(mask("col > 4", arr.clone()), vec![false, true, true]);
+        assert_eq!(mask("col < 5", arr.clone()), vec![true, false, false]);
+        assert_eq!(mask("col = 5", arr), vec![false, true, false]);
+    }
+
+    #[test]
+    fn uint32_comparison() {
+        let arr = Arc::new(UInt32Array::from(vec![10u32, 20, 30]));
+        assert_eq!(mask("col > 15", arr), vec![false, true, true]);
+    }
+
+    #[test]
+    fn timestamp_comparison() {
+        let arr = Arc::new(TimestampNanosecondArray::from(vec![100i64, 200, 300]));
+        assert_eq!(mask("col < 250", arr), vec![true, true, false]);
+    }
+
+    #[test]
+    fn unsupported_column_type_errors() {
+        let arr = Arc::new(BooleanArray::from(vec![true, false]));
+        let bat = batch("col", arr);
+        let err = compile_predicate(&bat, "col = true").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn malformed_expression_errors() {
+        let err = split_predicate("no operator here").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
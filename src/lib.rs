@@ -4,19 +4,23 @@ use std::sync::Arc;
 use std::time::SystemTime;
 
 use io::BufRead;
+use io::Read;
 
 use std::path::Path;
 
 use arrow::datatypes::DataType;
 use arrow::datatypes::Field;
+use arrow::datatypes::Fields;
 use arrow::datatypes::Schema;
 use arrow::datatypes::SchemaRef;
 use arrow::datatypes::TimeUnit;
 
 use arrow::array::Array;
+use arrow::array::BinaryBuilder;
 use arrow::array::BooleanBuilder;
+use arrow::array::MapBuilder;
 use arrow::array::StringBuilder;
-use arrow::array::TimestampSecondBuilder;
+use arrow::array::TimestampNanosecondBuilder;
 use arrow::array::UInt32Builder;
 use arrow::array::UInt64Builder;
 
@@ -29,6 +33,15 @@ where
     std::fs::metadata(p)
 }
 
+/// Like [`path2meta`] but does not follow symlinks, so a symlink reports its
+/// own metadata (and [`FileMeta::file_type`] can return [`FileType::Symlink`]).
+pub fn path2symlink_meta<P>(p: P) -> Result<Metadata, io::Error>
+where
+    P: AsRef<Path>,
+{
+    std::fs::symlink_metadata(p)
+}
+
 pub enum FileType {
     Dir,
     File,
@@ -86,12 +99,148 @@ impl<'a> FileMeta<'a> {
     pub fn gid(&self) -> u32 {
         std::os::unix::fs::MetadataExt::gid(self.0)
     }
+
+    pub fn atime_nanos(&self) -> i64 {
+        use std::os::unix::fs::MetadataExt;
+        self.0.atime() * 1_000_000_000 + self.0.atime_nsec()
+    }
+    pub fn mtime_nanos(&self) -> i64 {
+        use std::os::unix::fs::MetadataExt;
+        self.0.mtime() * 1_000_000_000 + self.0.mtime_nsec()
+    }
+    pub fn ctime_nanos(&self) -> i64 {
+        use std::os::unix::fs::MetadataExt;
+        self.0.ctime() * 1_000_000_000 + self.0.ctime_nsec()
+    }
+
+    pub fn blksize(&self) -> u64 {
+        std::os::unix::fs::MetadataExt::blksize(self.0)
+    }
+    pub fn blocks(&self) -> u64 {
+        std::os::unix::fs::MetadataExt::blocks(self.0)
+    }
+    pub fn ino(&self) -> u64 {
+        std::os::unix::fs::MetadataExt::ino(self.0)
+    }
+    pub fn dev(&self) -> u64 {
+        std::os::unix::fs::MetadataExt::dev(self.0)
+    }
+    pub fn rdev(&self) -> u64 {
+        std::os::unix::fs::MetadataExt::rdev(self.0)
+    }
 }
 
 pub fn stdin2lines() -> impl Iterator<Item = Result<String, io::Error>> {
     io::stdin().lock().lines()
 }
 
+/// Lists and reads every extended attribute attached to `p`.
+///
+/// Returns `None` when the filesystem does not support extended attributes or
+/// the attribute namespace is unreadable, so callers can store a null row
+/// rather than conflating "no xattrs" with "unsupported". Individual keys that
+/// disappear between listing and reading are silently dropped.
+#[cfg(unix)]
+pub fn path2xattrs<P>(p: P) -> Option<Vec<(String, Vec<u8>)>>
+where
+    P: AsRef<Path>,
+{
+    let names = xattr::list(&p).ok()?;
+    let mut out = Vec::new();
+    for name in names {
+        let key = name.to_string_lossy().into_owned();
+        if let Ok(Some(val)) = xattr::get(&p, &name) {
+            out.push((key, val));
+        }
+    }
+    Some(out)
+}
+
+/// Iterator adapter that expands directory inputs into their descendants.
+///
+/// It sits between [`stdin2lines`] and [`lines2batch_iter`]: each input line is
+/// treated as a root path, and whenever a popped entry is a directory it is
+/// replaced on the stack by its children. Traversal is driven by an explicit
+/// work stack rather than recursion so that arbitrarily deep trees cannot
+/// overflow the call stack. A read error on one subtree is surfaced as an `Err`
+/// item and the walk continues with the remaining stack.
+///
+/// Each yielded item pairs the path with its traversal depth (`0` for a root
+/// input, incremented for every level below it) so the depth can flow into the
+/// `depth` column.
+pub struct Walk<I> {
+    lines: I,
+    stack: Vec<(std::path::PathBuf, u32)>,
+    pending: std::collections::VecDeque<io::Error>,
+    max_depth: Option<u32>,
+    follow_symlinks: bool,
+}
+
+/// Wraps `lines` so that directory entries are expanded recursively.
+///
+/// `max_depth` limits how many levels below each root are visited (`None` is
+/// unbounded); `follow_symlinks` selects [`std::fs::metadata`] over
+/// [`std::fs::symlink_metadata`] when deciding whether an entry is a directory.
+pub fn walk<I>(lines: I, max_depth: Option<u32>, follow_symlinks: bool) -> Walk<I>
+where
+    I: Iterator<Item = Result<String, io::Error>>,
+{
+    Walk {
+        lines,
+        stack: Vec::new(),
+        pending: std::collections::VecDeque::new(),
+        max_depth,
+        follow_symlinks,
+    }
+}
+
+impl<I> Iterator for Walk<I>
+where
+    I: Iterator<Item = Result<String, io::Error>>,
+{
+    type Item = Result<(String, Option<u32>), io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.pending.pop_front() {
+            return Some(Err(e));
+        }
+
+        let (path, depth) = match self.stack.pop() {
+            Some(pd) => pd,
+            None => match self.lines.next()? {
+                Ok(line) => (std::path::PathBuf::from(line), 0),
+                Err(e) => return Some(Err(e)),
+            },
+        };
+
+        let within_depth = self.max_depth.map(|m| depth < m).unwrap_or(true);
+        if within_depth {
+            let md = if self.follow_symlinks {
+                std::fs::metadata(&path)
+            } else {
+                std::fs::symlink_metadata(&path)
+            };
+            if let Ok(m) = md {
+                if m.is_dir() {
+                    match std::fs::read_dir(&path) {
+                        Ok(rd) => {
+                            for ent in rd {
+                                match ent {
+                                    Ok(e) => self.stack.push((e.path(), depth + 1)),
+                                    Err(e) => self.pending.push_back(e),
+                                }
+                            }
+                        }
+                        Err(e) => self.pending.push_back(e),
+                    }
+                }
+            }
+        }
+
+        Some(Ok((path.to_string_lossy().into_owned(), Some(depth))))
+    }
+}
+
 pub fn schema() -> SchemaRef {
     Schema::new(vec![
         Field::new("path", DataType::Utf8, false),
@@ -102,7 +251,43 @@ pub fn schema() -> SchemaRef {
         Field::new("len", DataType::UInt64, false),
         Field::new("uid", DataType::UInt32, true),
         Field::new("gid", DataType::UInt32, true),
-        Field::new("mtime", DataType::Timestamp(TimeUnit::Second, None), true),
+        Field::new(
+            "atime",
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            true,
+        ),
+        Field::new(
+            "mtime",
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            true,
+        ),
+        Field::new(
+            "ctime",
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            true,
+        ),
+        Field::new("blksize", DataType::UInt64, true),
+        Field::new("blocks", DataType::UInt64, true),
+        Field::new("ino", DataType::UInt64, true),
+        Field::new("dev", DataType::UInt64, true),
+        Field::new("rdev", DataType::UInt64, true),
+        Field::new("depth", DataType::UInt32, true),
+        Field::new(
+            "xattrs",
+            DataType::Map(
+                Arc::new(Field::new(
+                    "entries",
+                    DataType::Struct(Fields::from(vec![
+                        Field::new("keys", DataType::Utf8, false),
+                        Field::new("values", DataType::Binary, true),
+                    ])),
+                    false,
+                )),
+                false,
+            ),
+            true,
+        ),
+        Field::new("symlink_target", DataType::Utf8, true),
     ])
     .into()
 }
@@ -112,14 +297,53 @@ pub fn lines2batch<I>(
     lines: &mut I,
     schema: SchemaRef,
     bldr: &mut Builder,
+    xattrs: bool,
+    no_follow: bool,
+    recursive: bool,
 ) -> Result<Option<RecordBatch>, io::Error>
 where
-    I: Iterator<Item = Result<String, io::Error>>,
+    I: Iterator<Item = Result<(String, Option<u32>), io::Error>>,
 {
     for rline in lines {
-        let line: String = rline?;
-        let meta: Metadata = path2meta(&line)?;
+        // In recursive mode an unreadable subtree (surfaced by `Walk` as an
+        // `Err`) or a vanished descendant is logged and skipped so a single
+        // failure does not abort the walk. Outside recursive mode every error
+        // is propagated.
+        let (line, depth) = match rline {
+            Ok(ld) => ld,
+            Err(e) if recursive => {
+                eprintln!("skipping unreadable entry: {e}");
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let meta: Metadata = match if no_follow {
+            path2symlink_meta(&line)
+        } else {
+            path2meta(&line)
+        } {
+            Ok(m) => m,
+            Err(e) if recursive => {
+                eprintln!("skipping {line}: {e}");
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
         let fmet = FileMeta(&meta);
+        let symlink_target = match fmet.file_type() {
+            FileType::Symlink => std::fs::read_link(&line)
+                .ok()
+                .map(|t| t.to_string_lossy().into_owned()),
+            _ => None,
+        };
+        if xattrs {
+            match path2xattrs(&line) {
+                Some(entries) => bldr.append_xattrs(entries.into_iter())?,
+                None => bldr.append_xattrs_null()?,
+            }
+        } else {
+            bldr.append_xattrs_null()?;
+        }
         bldr.append_path(line);
         bldr.append_type(fmet.file_type().name());
         bldr.append_read_only(fmet.read_only());
@@ -128,12 +352,16 @@ where
         bldr.append_len(meta.len());
         bldr.append_uid(Some(fmet.uid()));
         bldr.append_gid(Some(fmet.gid()));
-        let mtime_secs = meta
-            .modified()?
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .ok()
-            .map(|d| d.as_secs() as i64);
-        bldr.append_mtime(mtime_secs);
+        bldr.append_atime(Some(fmet.atime_nanos()));
+        bldr.append_mtime(Some(fmet.mtime_nanos()));
+        bldr.append_ctime(Some(fmet.ctime_nanos()));
+        bldr.append_blksize(Some(fmet.blksize()));
+        bldr.append_blocks(Some(fmet.blocks()));
+        bldr.append_ino(Some(fmet.ino()));
+        bldr.append_dev(Some(fmet.dev()));
+        bldr.append_rdev(Some(fmet.rdev()));
+        bldr.append_depth(depth);
+        bldr.append_symlink_target(symlink_target);
     }
 
     if bldr.is_empty() {
@@ -148,12 +376,23 @@ where
     let alen: Arc<dyn Array> = bldr.finish_len();
     let auid: Arc<dyn Array> = bldr.finish_uid();
     let agid: Arc<dyn Array> = bldr.finish_gid();
+    let aatime: Arc<dyn Array> = bldr.finish_atime();
     let amtime: Arc<dyn Array> = bldr.finish_mtime();
+    let actime: Arc<dyn Array> = bldr.finish_ctime();
+    let ablksize: Arc<dyn Array> = bldr.finish_blksize();
+    let ablocks: Arc<dyn Array> = bldr.finish_blocks();
+    let aino: Arc<dyn Array> = bldr.finish_ino();
+    let adev: Arc<dyn Array> = bldr.finish_dev();
+    let ardev: Arc<dyn Array> = bldr.finish_rdev();
+    let adepth: Arc<dyn Array> = bldr.finish_depth();
+    let axattrs: Arc<dyn Array> = bldr.finish_xattrs();
+    let asymlink_target: Arc<dyn Array> = bldr.finish_symlink_target();
 
     RecordBatch::try_new(
         schema,
         vec![
-            apath, atype, aread_only, amode, anlink, alen, auid, agid, amtime,
+            apath, atype, aread_only, amode, anlink, alen, auid, agid, aatime, amtime, actime,
+            ablksize, ablocks, aino, adev, ardev, adepth, axattrs, asymlink_target,
         ],
     )
     .map_err(io::Error::other)
@@ -169,7 +408,49 @@ pub struct Builder {
     pub len: UInt64Builder,
     pub uid: UInt32Builder,
     pub gid: UInt32Builder,
-    pub mtime: TimestampSecondBuilder,
+    pub atime: TimestampNanosecondBuilder,
+    pub mtime: TimestampNanosecondBuilder,
+    pub ctime: TimestampNanosecondBuilder,
+    pub blksize: UInt64Builder,
+    pub blocks: UInt64Builder,
+    pub ino: UInt64Builder,
+    pub dev: UInt64Builder,
+    pub rdev: UInt64Builder,
+    pub depth: UInt32Builder,
+    pub xattrs: MapBuilder<StringBuilder, BinaryBuilder>,
+    pub symlink_target: StringBuilder,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Builder {
+            path: StringBuilder::new(),
+            file_type: StringBuilder::new(),
+            read_only: BooleanBuilder::new(),
+            mode: UInt32Builder::new(),
+            nlink: UInt64Builder::new(),
+            len: UInt64Builder::new(),
+            uid: UInt32Builder::new(),
+            gid: UInt32Builder::new(),
+            atime: TimestampNanosecondBuilder::new(),
+            mtime: TimestampNanosecondBuilder::new(),
+            ctime: TimestampNanosecondBuilder::new(),
+            blksize: UInt64Builder::new(),
+            blocks: UInt64Builder::new(),
+            ino: UInt64Builder::new(),
+            dev: UInt64Builder::new(),
+            rdev: UInt64Builder::new(),
+            depth: UInt32Builder::new(),
+            xattrs: MapBuilder::new(None, StringBuilder::new(), BinaryBuilder::new()),
+            symlink_target: StringBuilder::new(),
+        }
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Builder {
@@ -198,9 +479,49 @@ impl Builder {
     pub fn append_gid(&mut self, l: Option<u32>) {
         self.gid.append_option(l)
     }
+    pub fn append_atime(&mut self, t: Option<i64>) {
+        self.atime.append_option(t)
+    }
     pub fn append_mtime(&mut self, t: Option<i64>) {
         self.mtime.append_option(t)
     }
+    pub fn append_ctime(&mut self, t: Option<i64>) {
+        self.ctime.append_option(t)
+    }
+    pub fn append_blksize(&mut self, b: Option<u64>) {
+        self.blksize.append_option(b)
+    }
+    pub fn append_blocks(&mut self, b: Option<u64>) {
+        self.blocks.append_option(b)
+    }
+    pub fn append_ino(&mut self, i: Option<u64>) {
+        self.ino.append_option(i)
+    }
+    pub fn append_dev(&mut self, d: Option<u64>) {
+        self.dev.append_option(d)
+    }
+    pub fn append_rdev(&mut self, d: Option<u64>) {
+        self.rdev.append_option(d)
+    }
+    pub fn append_depth(&mut self, d: Option<u32>) {
+        self.depth.append_option(d)
+    }
+    pub fn append_xattrs(
+        &mut self,
+        entries: impl Iterator<Item = (String, Vec<u8>)>,
+    ) -> Result<(), io::Error> {
+        for (k, v) in entries {
+            self.xattrs.keys().append_value(k);
+            self.xattrs.values().append_value(v);
+        }
+        self.xattrs.append(true).map_err(io::Error::other)
+    }
+    pub fn append_xattrs_null(&mut self) -> Result<(), io::Error> {
+        self.xattrs.append(false).map_err(io::Error::other)
+    }
+    pub fn append_symlink_target(&mut self, t: Option<String>) {
+        self.symlink_target.append_option(t)
+    }
 }
 
 impl Builder {
@@ -235,9 +556,39 @@ impl Builder {
     pub fn finish_gid(&mut self) -> Arc<dyn Array> {
         Arc::new(self.gid.finish())
     }
+    pub fn finish_atime(&mut self) -> Arc<dyn Array> {
+        Arc::new(self.atime.finish())
+    }
     pub fn finish_mtime(&mut self) -> Arc<dyn Array> {
         Arc::new(self.mtime.finish())
     }
+    pub fn finish_ctime(&mut self) -> Arc<dyn Array> {
+        Arc::new(self.ctime.finish())
+    }
+    pub fn finish_blksize(&mut self) -> Arc<dyn Array> {
+        Arc::new(self.blksize.finish())
+    }
+    pub fn finish_blocks(&mut self) -> Arc<dyn Array> {
+        Arc::new(self.blocks.finish())
+    }
+    pub fn finish_ino(&mut self) -> Arc<dyn Array> {
+        Arc::new(self.ino.finish())
+    }
+    pub fn finish_dev(&mut self) -> Arc<dyn Array> {
+        Arc::new(self.dev.finish())
+    }
+    pub fn finish_rdev(&mut self) -> Arc<dyn Array> {
+        Arc::new(self.rdev.finish())
+    }
+    pub fn finish_depth(&mut self) -> Arc<dyn Array> {
+        Arc::new(self.depth.finish())
+    }
+    pub fn finish_xattrs(&mut self) -> Arc<dyn Array> {
+        Arc::new(self.xattrs.finish())
+    }
+    pub fn finish_symlink_target(&mut self) -> Arc<dyn Array> {
+        Arc::new(self.symlink_target.finish())
+    }
 }
 
 #[cfg(unix)]
@@ -245,25 +596,21 @@ pub fn lines2batch_iter<I>(
     lines: I,
     schema: SchemaRef,
     batch_size: usize,
+    xattrs: bool,
+    no_follow: bool,
+    recursive: bool,
 ) -> Result<impl Iterator<Item = Result<RecordBatch, io::Error>>, io::Error>
 where
-    I: Iterator<Item = Result<String, io::Error>>,
+    I: Iterator<Item = Result<(String, Option<u32>), io::Error>>,
 {
     Ok(Lines2BatchIter {
         lines,
         schema,
         batch_size,
-        bldr: Builder {
-            path: StringBuilder::new(),
-            file_type: StringBuilder::new(),
-            read_only: BooleanBuilder::new(),
-            mode: UInt32Builder::new(),
-            nlink: UInt64Builder::new(),
-            len: UInt64Builder::new(),
-            uid: UInt32Builder::new(),
-            gid: UInt32Builder::new(),
-            mtime: TimestampSecondBuilder::new(),
-        },
+        xattrs,
+        no_follow,
+        recursive,
+        bldr: Builder::new(),
     })
 }
 
@@ -272,20 +619,47 @@ struct Lines2BatchIter<I> {
     lines: I,
     schema: SchemaRef,
     batch_size: usize,
+    xattrs: bool,
+    no_follow: bool,
+    recursive: bool,
     bldr: Builder,
 }
 
 #[cfg(unix)]
 impl<I> Iterator for Lines2BatchIter<I>
 where
-    I: Iterator<Item = Result<String, io::Error>>,
+    I: Iterator<Item = Result<(String, Option<u32>), io::Error>>,
 {
     type Item = Result<RecordBatch, io::Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let mut taken = (&mut self.lines).take(self.batch_size);
+        // Counts the inputs consumed, so that a batch whose every input was
+        // skipped in recursive mode is followed by the next one instead of
+        // ending the stream.
+        let mut seen = 0usize;
+        let mut taken = (&mut taken).inspect(|_| seen += 1);
 
-        let robat = lines2batch(&mut taken, self.schema.clone(), &mut self.bldr);
+        let mut robat = lines2batch(
+            &mut taken,
+            self.schema.clone(),
+            &mut self.bldr,
+            self.xattrs,
+            self.no_follow,
+            self.recursive,
+        );
+        while self.recursive && seen > 0 && matches!(robat, Ok(None)) {
+            seen = 0;
+            let mut retry = (&mut self.lines).take(self.batch_size).inspect(|_| seen += 1);
+            robat = lines2batch(
+                &mut retry,
+                self.schema.clone(),
+                &mut self.bldr,
+                self.xattrs,
+                self.no_follow,
+                self.recursive,
+            );
+        }
 
         match robat {
             Err(e) => Some(Err(e)),
@@ -294,3 +668,575 @@ where
         }
     }
 }
+
+/// A single logical entry parsed from a tar stream.
+///
+/// Only the fields this crate's schema models are retained; name and the
+/// high-resolution times may be overridden by a preceding PAX extended header
+/// or GNU long-name block.
+#[derive(Debug)]
+struct TarEntry {
+    name: String,
+    typeflag: u8,
+    mode: u32,
+    uid: u32,
+    gid: u32,
+    size: u64,
+    mtime_nanos: i64,
+    atime_nanos: Option<i64>,
+    ctime_nanos: Option<i64>,
+    link_target: Option<String>,
+}
+
+impl TarEntry {
+    fn type_name(&self) -> &'static str {
+        match self.typeflag {
+            b'0' | b'\0' => FileType::File.name(),
+            b'5' => FileType::Dir.name(),
+            b'2' => FileType::Symlink.name(),
+            _ => FileType::Unspecified.name(),
+        }
+    }
+
+    fn read_only(&self) -> bool {
+        // No owner-write bit set.
+        self.mode & 0o200 == 0
+    }
+}
+
+fn octal(field: &[u8]) -> u64 {
+    // POSIX permits numeric fields to be padded with leading spaces or NULs
+    // (as BSD/macOS/star tars emit), so skip them before accumulating digits.
+    let mut acc: u64 = 0;
+    for &b in field.iter().skip_while(|&&b| b == b' ' || b == 0) {
+        match b {
+            b'0'..=b'7' => acc = acc * 8 + u64::from(b - b'0'),
+            _ => break,
+        }
+    }
+    acc
+}
+
+fn is_zero_block(block: &[u8; 512]) -> bool {
+    block.iter().all(|&b| b == 0)
+}
+
+/// Reads exactly one 512-byte tar block.
+///
+/// Returns `Ok(None)` on a clean end-of-stream at a block boundary and a
+/// [`io::ErrorKind::UnexpectedEof`] error when the stream ends mid-block
+/// (a truncated header), rather than panicking.
+fn read_block<R: Read>(reader: &mut R) -> Result<Option<[u8; 512]>, io::Error> {
+    let mut block = [0u8; 512];
+    let mut filled = 0;
+    while filled < 512 {
+        match reader.read(&mut block[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    match filled {
+        0 => Ok(None),
+        512 => Ok(Some(block)),
+        _ => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "truncated tar header",
+        )),
+    }
+}
+
+/// Overrides applied to the next regular entry by a preceding PAX/GNU header.
+#[derive(Default)]
+struct TarOverrides {
+    name: Option<String>,
+    mtime_nanos: Option<i64>,
+    atime_nanos: Option<i64>,
+    ctime_nanos: Option<i64>,
+    size: Option<u64>,
+}
+
+fn pax_time_nanos(v: &str) -> Option<i64> {
+    let (secs, frac) = match v.split_once('.') {
+        Some((s, f)) => (s, f),
+        None => (v, ""),
+    };
+    let secs: i64 = secs.parse().ok()?;
+    let mut nanos: i64 = 0;
+    for (i, c) in frac.chars().take(9).enumerate() {
+        let d = c.to_digit(10)? as i64;
+        nanos += d * 10i64.pow(8 - i as u32);
+    }
+    Some(secs * 1_000_000_000 + nanos)
+}
+
+fn apply_pax(records: &[u8], ov: &mut TarOverrides) {
+    let mut rest = records;
+    while !rest.is_empty() {
+        // Each record is "LEN key=value\n" where LEN counts the whole record.
+        let sp = match rest.iter().position(|&b| b == b' ') {
+            Some(p) => p,
+            None => break,
+        };
+        let len: usize = match std::str::from_utf8(&rest[..sp]).ok().and_then(|s| s.parse().ok()) {
+            Some(l) if l > sp && l <= rest.len() => l,
+            _ => break,
+        };
+        let body = &rest[sp + 1..len];
+        if let Ok(kv) = std::str::from_utf8(body) {
+            let kv = kv.strip_suffix('\n').unwrap_or(kv);
+            if let Some((key, value)) = kv.split_once('=') {
+                match key {
+                    "path" => ov.name = Some(value.to_string()),
+                    "mtime" => ov.mtime_nanos = pax_time_nanos(value),
+                    "atime" => ov.atime_nanos = pax_time_nanos(value),
+                    "ctime" => ov.ctime_nanos = pax_time_nanos(value),
+                    "size" => ov.size = value.parse().ok(),
+                    _ => {}
+                }
+            }
+        }
+        rest = &rest[len..];
+    }
+}
+
+/// Reads `size` bytes of entry data (rounded up to the block size) and returns
+/// the unpadded payload.
+fn read_data<R: Read>(reader: &mut R, size: u64) -> Result<Vec<u8>, io::Error> {
+    let blocks = size.div_ceil(512);
+    let mut out = Vec::with_capacity(size as usize);
+    for _ in 0..blocks {
+        match read_block(reader)? {
+            Some(b) => out.extend_from_slice(&b),
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "truncated tar data",
+                ))
+            }
+        }
+    }
+    out.truncate(size as usize);
+    Ok(out)
+}
+
+/// Reads the next logical entry, consuming any PAX/GNU extension blocks and the
+/// regular entry's data payload. Returns `Ok(None)` at end-of-archive.
+fn next_tar_entry<R: Read>(reader: &mut R) -> Result<Option<TarEntry>, io::Error> {
+    let mut ov = TarOverrides::default();
+    loop {
+        let block = match read_block(reader)? {
+            Some(b) => b,
+            None => return Ok(None),
+        };
+        if is_zero_block(&block) {
+            // First of the two trailing zero blocks marks end-of-archive.
+            return Ok(None);
+        }
+
+        let mode = octal(&block[100..108]) as u32;
+        let uid = octal(&block[108..116]) as u32;
+        let gid = octal(&block[116..124]) as u32;
+        let size = ov.size.unwrap_or_else(|| octal(&block[124..136]));
+        let mtime_secs = octal(&block[136..148]) as i64;
+        let typeflag = block[156];
+
+        match typeflag {
+            b'x' | b'g' => {
+                let data = read_data(reader, octal(&block[124..136]))?;
+                apply_pax(&data, &mut ov);
+                continue;
+            }
+            b'L' => {
+                let data = read_data(reader, octal(&block[124..136]))?;
+                let name = String::from_utf8_lossy(&data)
+                    .trim_end_matches('\0')
+                    .to_string();
+                ov.name = Some(name);
+                continue;
+            }
+            b'K' => {
+                // GNU long link name: not modelled, skip its payload.
+                let _ = read_data(reader, octal(&block[124..136]))?;
+                continue;
+            }
+            _ => {}
+        }
+
+        let name = ov.name.take().unwrap_or_else(|| {
+            let prefix = cstr(&block[345..500]);
+            let name = cstr(&block[0..100]);
+            if prefix.is_empty() {
+                name
+            } else {
+                format!("{prefix}/{name}")
+            }
+        });
+
+        // Consume the data payload of a regular entry before the next header.
+        let _ = read_data(reader, size)?;
+
+        return Ok(Some(TarEntry {
+            name,
+            typeflag,
+            mode,
+            uid,
+            gid,
+            size,
+            mtime_nanos: ov.mtime_nanos.unwrap_or(mtime_secs * 1_000_000_000),
+            atime_nanos: ov.atime_nanos,
+            ctime_nanos: ov.ctime_nanos,
+            link_target: if typeflag == b'2' {
+                Some(cstr(&block[157..257]))
+            } else {
+                None
+            },
+        }));
+    }
+}
+
+/// Decodes a NUL-terminated (or field-filling) tar string field.
+fn cstr(field: &[u8]) -> String {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+fn tar_entry2builder(e: &TarEntry, bldr: &mut Builder) {
+    bldr.append_xattrs_null().ok();
+    bldr.append_path(e.name.clone());
+    bldr.append_type(e.type_name());
+    bldr.append_read_only(e.read_only());
+    bldr.append_mode(Some(e.mode));
+    bldr.append_nlink(None);
+    bldr.append_len(e.size);
+    bldr.append_uid(Some(e.uid));
+    bldr.append_gid(Some(e.gid));
+    bldr.append_atime(e.atime_nanos);
+    bldr.append_mtime(Some(e.mtime_nanos));
+    bldr.append_ctime(e.ctime_nanos);
+    bldr.append_blksize(None);
+    bldr.append_blocks(None);
+    bldr.append_ino(None);
+    bldr.append_dev(None);
+    bldr.append_rdev(None);
+    bldr.append_depth(None);
+    bldr.append_symlink_target(e.link_target.clone());
+}
+
+/// Parses a POSIX/GNU tar stream and yields the same Arrow schema as
+/// [`lines2batch_iter`], so an archive can be inventoried without extraction.
+pub fn tar_entries2batch_iter<R>(
+    reader: R,
+    schema: SchemaRef,
+    batch_size: usize,
+) -> Result<impl Iterator<Item = Result<RecordBatch, io::Error>>, io::Error>
+where
+    R: Read,
+{
+    Ok(TarBatchIter {
+        reader,
+        schema,
+        batch_size,
+        bldr: Builder::new(),
+        done: false,
+    })
+}
+
+struct TarBatchIter<R> {
+    reader: R,
+    schema: SchemaRef,
+    batch_size: usize,
+    bldr: Builder,
+    done: bool,
+}
+
+impl<R> Iterator for TarBatchIter<R>
+where
+    R: Read,
+{
+    type Item = Result<RecordBatch, io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut count = 0;
+        while count < self.batch_size {
+            match next_tar_entry(&mut self.reader) {
+                Ok(Some(e)) => {
+                    tar_entry2builder(&e, &mut self.bldr);
+                    count += 1;
+                }
+                Ok(None) => {
+                    self.done = true;
+                    break;
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+
+        if count == 0 {
+            return None;
+        }
+
+        let apath = self.bldr.finish_path();
+        let atype = self.bldr.finish_type();
+        let aread_only = self.bldr.finish_read_only();
+        let amode = self.bldr.finish_mode();
+        let anlink = self.bldr.finish_nlink();
+        let alen = self.bldr.finish_len();
+        let auid = self.bldr.finish_uid();
+        let agid = self.bldr.finish_gid();
+        let aatime = self.bldr.finish_atime();
+        let amtime = self.bldr.finish_mtime();
+        let actime = self.bldr.finish_ctime();
+        let ablksize = self.bldr.finish_blksize();
+        let ablocks = self.bldr.finish_blocks();
+        let aino = self.bldr.finish_ino();
+        let adev = self.bldr.finish_dev();
+        let ardev = self.bldr.finish_rdev();
+        let adepth = self.bldr.finish_depth();
+        let axattrs = self.bldr.finish_xattrs();
+        let asymlink_target = self.bldr.finish_symlink_target();
+
+        let robat = RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                apath, atype, aread_only, amode, anlink, alen, auid, agid, aatime, amtime, actime,
+                ablksize, ablocks, aino, adev, ardev, adepth, axattrs, asymlink_target,
+            ],
+        )
+        .map_err(io::Error::other);
+
+        Some(robat)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn octal_plain() {
+        assert_eq!(octal(b"0000644\0"), 0o644);
+    }
+
+    #[test]
+    fn octal_leading_space() {
+        // BSD/macOS/star tars left-pad numeric fields with spaces.
+        assert_eq!(octal(b"    644\0"), 0o644);
+        assert_eq!(octal(b"       0"), 0);
+        assert_eq!(octal(b" 0001750 "), 0o1750);
+    }
+
+    #[test]
+    fn pax_time_fraction() {
+        assert_eq!(
+            pax_time_nanos("1609459200.123456789"),
+            Some(1_609_459_200 * 1_000_000_000 + 123_456_789)
+        );
+        assert_eq!(pax_time_nanos("5"), Some(5_000_000_000));
+    }
+
+    fn pax_record(kv: &str) -> String {
+        // The length prefix counts the whole "LEN key=value\n" record.
+        let mut len = kv.len() + 3;
+        loop {
+            let cand = len.to_string().len() + kv.len() + 2;
+            if cand == len {
+                break;
+            }
+            len = cand;
+        }
+        format!("{len} {kv}\n")
+    }
+
+    #[test]
+    fn apply_pax_overrides() {
+        let mut records = String::new();
+        records.push_str(&pax_record("path=long/name/file.txt"));
+        records.push_str(&pax_record("mtime=1609459200.5"));
+        records.push_str(&pax_record("size=4096"));
+        let mut ov = TarOverrides::default();
+        apply_pax(records.as_bytes(), &mut ov);
+        assert_eq!(ov.name.as_deref(), Some("long/name/file.txt"));
+        assert_eq!(ov.mtime_nanos, Some(1_609_459_200 * 1_000_000_000 + 500_000_000));
+        assert_eq!(ov.size, Some(4096));
+    }
+
+    fn put_octal(field: &mut [u8], v: u64) {
+        let s = format!("{v:o}");
+        field[..s.len()].copy_from_slice(s.as_bytes());
+    }
+
+    fn tar_header(name: &str, typeflag: u8, mode: u64, size: u64, mtime: u64) -> [u8; 512] {
+        let mut b = [0u8; 512];
+        b[..name.len()].copy_from_slice(name.as_bytes());
+        put_octal(&mut b[100..108], mode);
+        put_octal(&mut b[124..136], size);
+        put_octal(&mut b[136..148], mtime);
+        b[156] = typeflag;
+        b
+    }
+
+    #[test]
+    fn next_tar_entry_regular_file() {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&tar_header("hello.txt", b'0', 0o644, 5, 1_000));
+        let mut data = [0u8; 512];
+        data[..5].copy_from_slice(b"hello");
+        buf.extend_from_slice(&data);
+        // End-of-archive marker.
+        buf.extend_from_slice(&[0u8; 512]);
+        buf.extend_from_slice(&[0u8; 512]);
+
+        let mut cur = Cursor::new(buf);
+        let entry = next_tar_entry(&mut cur).unwrap().expect("one entry");
+        assert_eq!(entry.name, "hello.txt");
+        assert_eq!(entry.type_name(), "file");
+        assert_eq!(entry.mode, 0o644);
+        assert_eq!(entry.size, 5);
+        assert_eq!(entry.mtime_nanos, 1_000 * 1_000_000_000);
+        assert!(next_tar_entry(&mut cur).unwrap().is_none());
+    }
+
+    #[test]
+    fn next_tar_entry_truncated_header() {
+        // A partial block is a truncated header, not a clean end-of-archive.
+        let cur = Cursor::new(vec![b'x'; 100]);
+        let mut cur = cur;
+        let err = next_tar_entry(&mut cur).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn xattrs_null_vs_populated_rows() {
+        use arrow::array::{BinaryArray, MapArray, StringArray};
+
+        let mut bldr = Builder::new();
+        bldr.append_xattrs([("user.comment".to_string(), b"hi".to_vec())].into_iter())
+            .unwrap();
+        bldr.append_xattrs_null().unwrap();
+
+        let arr = bldr.finish_xattrs();
+        let map = arr.as_any().downcast_ref::<MapArray>().unwrap();
+        assert_eq!(map.len(), 2);
+
+        // First row carries one attribute, the second is a null map.
+        assert!(!map.is_null(0));
+        assert!(map.is_null(1));
+
+        let entries = map.value(0);
+        let keys = entries
+            .column(0)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let values = entries
+            .column(1)
+            .as_any()
+            .downcast_ref::<BinaryArray>()
+            .unwrap();
+        assert_eq!(keys.value(0), "user.comment");
+        assert_eq!(values.value(0), b"hi");
+    }
+
+    #[test]
+    fn symlink_target_recorded_under_no_follow() {
+        use arrow::array::StringArray;
+
+        let root = std::env::temp_dir().join(format!("symlink_tgt_{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let target = root.join("target.txt");
+        std::fs::write(&target, b"x").unwrap();
+        let link = root.join("link");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        let schema = schema();
+        let type_idx = schema.index_of("type").unwrap();
+        let tgt_idx = schema.index_of("symlink_target").unwrap();
+
+        let input = vec![Ok((link.to_string_lossy().into_owned(), None))];
+        let mut bldr = Builder::new();
+        let batch = lines2batch(
+            &mut input.into_iter(),
+            Arc::clone(&schema),
+            &mut bldr,
+            false,
+            true,
+            false,
+        )
+        .unwrap()
+        .expect("one row");
+
+        let types = batch
+            .column(type_idx)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let targets = batch
+            .column(tgt_idx)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(types.value(0), "symlink");
+        assert_eq!(targets.value(0), target.to_string_lossy());
+
+        // Following the link resolves to the regular file, so no target is kept.
+        let input = vec![Ok((link.to_string_lossy().into_owned(), None))];
+        let mut bldr = Builder::new();
+        let batch = lines2batch(
+            &mut input.into_iter(),
+            Arc::clone(&schema),
+            &mut bldr,
+            false,
+            false,
+            false,
+        )
+        .unwrap()
+        .expect("one row");
+        let types = batch
+            .column(type_idx)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        let targets = batch
+            .column(tgt_idx)
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(types.value(0), "file");
+        assert!(targets.is_null(0));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn walk_reports_depth() {
+        let root = std::env::temp_dir().join(format!("walk_depth_{}", std::process::id()));
+        let sub = root.join("sub");
+        std::fs::create_dir_all(&sub).unwrap();
+        std::fs::write(root.join("a.txt"), b"a").unwrap();
+        std::fs::write(sub.join("b.txt"), b"b").unwrap();
+
+        let input = vec![Ok(root.to_string_lossy().into_owned())];
+        let mut depths: std::collections::HashMap<String, u32> = std::collections::HashMap::new();
+        for item in walk(input.into_iter(), None, false) {
+            let (path, depth) = item.unwrap();
+            depths.insert(path, depth.unwrap());
+        }
+
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(depths.get(root.to_str().unwrap()), Some(&0));
+        assert_eq!(depths.get(root.join("a.txt").to_str().unwrap()), Some(&1));
+        assert_eq!(depths.get(sub.to_str().unwrap()), Some(&1));
+        assert_eq!(depths.get(sub.join("b.txt").to_str().unwrap()), Some(&2));
+    }
+}